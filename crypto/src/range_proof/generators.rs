@@ -1,11 +1,149 @@
-use curve25519_dalek::ristretto::RistrettoPoint;
+use curve25519_dalek::constants::RISTRETTO_BASEPOINT_POINT;
+use curve25519_dalek::ristretto::{RistrettoPoint, VartimeRistrettoPrecomputation};
+use curve25519_dalek::scalar::Scalar;
+use curve25519_dalek::traits::{MultiscalarMul, VartimePrecomputedMultiscalarMul};
 use digest::{ExtendableOutput, Input, XofReader};
 use sha3::{Sha3XofReader, Sha3_512, Shake256};
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+use thiserror::Error;
 
 /// Generators for Pedersen vector commitments.
 ///
 /// The code is copied from https://github.com/dalek-cryptography/bulletproofs for now...
 
+/// The maximum number of \\(\mathbf G\\) generators, and separately of
+/// \\(\mathbf H\\) generators, that a `BulletproofGens` is allowed to hold
+/// across all of its parties.
+///
+/// This bounds the size of a single (or aggregated) range proof and prevents a
+/// caller from requesting an allocation so large that it OOMs the node.
+pub const MAX_GENERATOR_LENGTH: usize = u32::MAX as usize;
+
+/// Bit-length of a single 128-bit range proof value, e.g. a 128-bit token
+/// amount.
+pub const BIT_LENGTH_128: usize = 128;
+
+/// Converts a 128-bit value into a `Scalar`, for committing amounts that
+/// exceed `u64::MAX` (e.g. 128-bit token amounts) under a 128-bit range
+/// proof's generators.
+pub fn scalar_from_u128(value: u128) -> Scalar {
+    let mut bytes = [0u8; 32];
+    bytes[..16].copy_from_slice(&value.to_le_bytes());
+    Scalar::from_bytes_mod_order(bytes)
+}
+
+/// Errors that can occur when constructing or extending `BulletproofGens`.
+#[derive(Error, Clone, Debug, Eq, PartialEq)]
+pub enum RangeProofGeneratorError {
+    /// Requested per-party generator capacity, or that capacity times the
+    /// party capacity, exceeds `MAX_GENERATOR_LENGTH`.
+    #[error("requested generator capacity exceeds the maximum allowed length")]
+    MaximumGeneratorLengthExceeded,
+    /// Requested generator capacity is zero, which would back a degenerate proof.
+    #[error("requested generator capacity must be greater than zero")]
+    EmptyGeneratorSet,
+    /// Requested party capacity exceeds `MAX_GENERATOR_LENGTH`.
+    #[error("requested party capacity exceeds the maximum allowed length")]
+    MaximumPartyCapacityExceeded,
+    /// Requested party capacity is zero, which would produce a `BulletproofGens`
+    /// with no party to share generators with.
+    #[error("requested party capacity must be greater than zero")]
+    EmptyPartySet,
+}
+
+/// Represents a pair of base points for Pedersen commitments.
+///
+/// The Pedersen commitment to a value \\(v\\) with blinding factor \\(\\tilde
+/// v\\) is \\(\operatorname{Com}(v) = v \cdot B + \tilde v \cdot
+/// \widetilde B\\), where \\(B\\) and \\(\widetilde B\\) are the two base
+/// points held here.
+#[allow(non_snake_case)]
+#[derive(Copy, Clone)]
+pub struct PedersenGens {
+    /// Base for the committed value.
+    pub B: RistrettoPoint,
+    /// Base for the blinding factor.
+    pub B_blinding: RistrettoPoint,
+}
+
+impl PedersenGens {
+    /// Creates a Pedersen commitment using the value scalar and a blinding
+    /// factor.
+    pub fn commit(&self, value: Scalar, blinding: Scalar) -> RistrettoPoint {
+        RistrettoPoint::multiscalar_mul(&[value, blinding], &[self.B, self.B_blinding])
+    }
+
+    /// Creates a Pedersen commitment to a 128-bit value and a blinding
+    /// factor, for use with a 128-bit range proof.
+    pub fn commit_u128(&self, value: u128, blinding: Scalar) -> RistrettoPoint {
+        self.commit(scalar_from_u128(value), blinding)
+    }
+}
+
+impl Default for PedersenGens {
+    fn default() -> Self {
+        PedersenGens {
+            B: RISTRETTO_BASEPOINT_POINT,
+            B_blinding: RistrettoPoint::hash_from_bytes::<Sha3_512>(
+                RISTRETTO_BASEPOINT_POINT.compress().as_bytes(),
+            ),
+        }
+    }
+}
+
+/// Maximum number of distinct value bases whose `2^i * B` table is kept in
+/// [`VALUE_BASE_POW_2_CACHE`]. Callers are expected to use a small, fixed
+/// number of bases (typically just the default one); this bounds the cache
+/// so a caller-controlled sequence of distinct bases (e.g. varying
+/// domain-separated bases per request) can't leak one table per base for
+/// the life of the process.
+const MAX_CACHED_VALUE_BASES: usize = 8;
+
+/// Cache of `2^i * B` tables (`i` in `0..BIT_LENGTH_128`), keyed by the
+/// compressed bytes of `B` so that each distinct value base's table is
+/// computed once by repeated doubling and reused thereafter, up to
+/// [`MAX_CACHED_VALUE_BASES`] distinct bases.
+static VALUE_BASE_POW_2_CACHE: LazyLock<
+    Mutex<HashMap<[u8; 32], Arc<[RistrettoPoint; BIT_LENGTH_128]>>>,
+> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+impl PedersenGens {
+    /// Returns the table of `2^i * B` for `i` in `0..BIT_LENGTH_128`, for
+    /// this instance's value base `B`.
+    ///
+    /// Fixed-bit-length range proof verification needs these powers of two
+    /// to form the powers-of-two vector; looking them up here is cheaper
+    /// than recomputing a scalar multiplication on every proof. The table is
+    /// computed once per distinct base and cached thereafter, so swapping in
+    /// a custom or domain-separated `B` still gets its own correct table
+    /// rather than the default base's. The cache holds at most
+    /// [`MAX_CACHED_VALUE_BASES`] distinct bases; once full, tables for new
+    /// bases are still computed correctly but are not retained.
+    pub fn value_base_pow_2(&self) -> Arc<[RistrettoPoint; BIT_LENGTH_128]> {
+        let key = self.B.compress().to_bytes();
+
+        let mut cache = VALUE_BASE_POW_2_CACHE.lock().unwrap();
+        if let Some(table) = cache.get(&key) {
+            return table.clone();
+        }
+
+        let mut table = [self.B; BIT_LENGTH_128];
+        let mut current = self.B;
+        for entry in table.iter_mut() {
+            *entry = current;
+            current += current;
+        }
+        let table = Arc::new(table);
+
+        if cache.len() < MAX_CACHED_VALUE_BASES {
+            cache.insert(key, table.clone());
+        }
+        table
+    }
+}
+
 struct GeneratorsChain {
     reader: Sha3XofReader,
 }
@@ -54,78 +192,284 @@ impl Iterator for GeneratorsChain {
     }
 }
 
+/// The `BulletproofGens` struct contains all the generators needed to
+/// aggregate up to `party_capacity` range proofs of up to `gens_capacity`
+/// bits each into a single proof.
+///
+/// Each party's generators are drawn from their own [`GeneratorsChain`], so
+/// that a party's share of the generators can be extended independently of
+/// every other party's, and so that adding parties never changes the
+/// generators already assigned to existing ones.
 #[allow(non_snake_case)]
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub struct BulletproofGens {
-    /// The maximum number of usable generators.
+    /// The maximum number of usable generators for each party.
     pub gens_capacity: usize,
-    /// Precomputed \\(\mathbf G\\) generators.
-    G_vec: Vec<RistrettoPoint>,
-    /// Precomputed \\(\mathbf H\\) generators.
-    H_vec: Vec<RistrettoPoint>,
+    /// Number of values or parties.
+    pub party_capacity: usize,
+    /// Precomputed \\(\mathbf G\\) generators for each party.
+    G_vec: Vec<Vec<RistrettoPoint>>,
+    /// Precomputed \\(\mathbf H\\) generators for each party.
+    H_vec: Vec<Vec<RistrettoPoint>>,
 }
 
 impl BulletproofGens {
-    pub fn new(gens_capacity: usize) -> Self {
+    /// Creates a `BulletproofGens` with the given capacities, panicking if
+    /// either capacity is invalid. Prefer [`BulletproofGens::try_new`] to
+    /// handle the error instead of panicking.
+    pub fn new(gens_capacity: usize, party_capacity: usize) -> Self {
+        Self::try_new(gens_capacity, party_capacity).expect("failed to create `BulletproofGens`")
+    }
+
+    /// Creates a `BulletproofGens` with the given capacities, rejecting a
+    /// per-party capacity of `0` (a degenerate, 0-bit range proof) and a
+    /// `party_capacity` of `0`, as well as either dimension alone, or their
+    /// product (the total number of generators actually allocated), larger
+    /// than [`MAX_GENERATOR_LENGTH`].
+    pub fn try_new(
+        gens_capacity: usize,
+        party_capacity: usize,
+    ) -> Result<Self, RangeProofGeneratorError> {
+        if party_capacity == 0 {
+            return Err(RangeProofGeneratorError::EmptyPartySet);
+        }
+        if party_capacity > MAX_GENERATOR_LENGTH {
+            return Err(RangeProofGeneratorError::MaximumPartyCapacityExceeded);
+        }
+        Self::check_total_capacity(party_capacity, gens_capacity)?;
+
         let mut gens = BulletproofGens {
             gens_capacity: 0,
-            G_vec: Vec::new(),
-            H_vec: Vec::new(),
+            party_capacity,
+            G_vec: (0..party_capacity).map(|_| Vec::new()).collect(),
+            H_vec: (0..party_capacity).map(|_| Vec::new()).collect(),
         };
-        gens.increase_capacity(gens_capacity);
-        gens
+        gens.increase_capacity(gens_capacity)?;
+        Ok(gens)
     }
 
-    // pub fn new_aggregate(gens_capacities: Vec<usize>) -> Vec<BulletproofGens> {
-    //     let mut gens_vector = Vec::new();
-    //     for (capacity, i) in gens_capacities.iter().enumerate() {
-    //         gens_vector.push(BulletproofGens::new(capacity, &i.to_le_bytes()));
-    //     }
-    //     gens_vector
-    // }
+    /// Creates generators sized for `party_capacity` parties, each proving a
+    /// single 128-bit value (e.g. a 128-bit token amount), aggregated into
+    /// one combined proof.
+    pub fn new_128(party_capacity: usize) -> Self {
+        Self::new(BIT_LENGTH_128, party_capacity)
+    }
+
+    /// Rejects a `(party_capacity, gens_capacity)` pair whose product (the
+    /// total number of generators that would be allocated across all
+    /// parties) overflows or exceeds [`MAX_GENERATOR_LENGTH`], even though
+    /// each dimension individually stays within that bound.
+    fn check_total_capacity(
+        party_capacity: usize,
+        gens_capacity: usize,
+    ) -> Result<(), RangeProofGeneratorError> {
+        match party_capacity.checked_mul(gens_capacity) {
+            Some(total) if total <= MAX_GENERATOR_LENGTH => Ok(()),
+            _ => Err(RangeProofGeneratorError::MaximumGeneratorLengthExceeded),
+        }
+    }
+
+    /// Returns a view of the generators owned by the `j`-th party.
+    pub fn share(&self, j: usize) -> BulletproofGensShare<'_> {
+        BulletproofGensShare {
+            gens: self,
+            share: j,
+        }
+    }
 
     /// Increases the generators' capacity to the amount specified.
     /// If less than or equal to the current capacity, does nothing.
-    pub fn increase_capacity(&mut self, new_capacity: usize) {
+    ///
+    /// Extends every party's chain, so that existing parties keep the
+    /// generators they already have and simply gain more.
+    ///
+    /// Returns an error instead of allocating if `new_capacity` is `0`, or if
+    /// `new_capacity` or the total number of generators this would allocate
+    /// (`new_capacity * self.party_capacity`) exceeds
+    /// [`MAX_GENERATOR_LENGTH`].
+    pub fn increase_capacity(
+        &mut self,
+        new_capacity: usize,
+    ) -> Result<(), RangeProofGeneratorError> {
+        if new_capacity == 0 {
+            return Err(RangeProofGeneratorError::EmptyGeneratorSet);
+        }
+        if new_capacity > MAX_GENERATOR_LENGTH {
+            return Err(RangeProofGeneratorError::MaximumGeneratorLengthExceeded);
+        }
+        Self::check_total_capacity(self.party_capacity, new_capacity)?;
         if self.gens_capacity >= new_capacity {
-            return;
+            return Ok(());
         }
 
-        let mut label = [b'G'];
-        self.G_vec.extend(
-            &mut GeneratorsChain::new(&[label, [b'G']].concat())
-                .fast_forward(self.gens_capacity)
-                .take(new_capacity - self.gens_capacity),
-        );
+        for (j, G_j) in self.G_vec.iter_mut().enumerate() {
+            let party_index = (j as u32).to_le_bytes();
+            G_j.extend(
+                &mut GeneratorsChain::new(&[b"G".as_ref(), &party_index].concat())
+                    .fast_forward(self.gens_capacity)
+                    .take(new_capacity - self.gens_capacity),
+            );
+        }
 
-        self.H_vec.extend(
-            &mut GeneratorsChain::new(&[label, [b'H']].concat())
-                .fast_forward(self.gens_capacity)
-                .take(new_capacity - self.gens_capacity),
-        );
+        for (j, H_j) in self.H_vec.iter_mut().enumerate() {
+            let party_index = (j as u32).to_le_bytes();
+            H_j.extend(
+                &mut GeneratorsChain::new(&[b"H".as_ref(), &party_index].concat())
+                    .fast_forward(self.gens_capacity)
+                    .take(new_capacity - self.gens_capacity),
+            );
+        }
 
         self.gens_capacity = new_capacity;
+        Ok(())
     }
 
-    pub(crate) fn G(&self, n: usize) -> impl Iterator<Item = &RistrettoPoint> {
-        GensIter {
+    /// Returns an iterator over the aggregation of the parties' \\(\mathbf
+    /// G\\) generators, taking `n` generators from each of `m` parties.
+    pub(crate) fn G(&self, n: usize, m: usize) -> impl Iterator<Item = &RistrettoPoint> {
+        AggregatedGensIter {
             array: &self.G_vec,
             n,
+            m,
+            party_idx: 0,
             gen_idx: 0,
         }
     }
 
-    pub(crate) fn H(&self, n: usize) -> impl Iterator<Item = &RistrettoPoint> {
-        GensIter {
+    /// Returns an iterator over the aggregation of the parties' \\(\mathbf
+    /// H\\) generators, taking `n` generators from each of `m` parties.
+    pub(crate) fn H(&self, n: usize, m: usize) -> impl Iterator<Item = &RistrettoPoint> {
+        AggregatedGensIter {
             array: &self.H_vec,
             n,
+            m,
+            party_idx: 0,
+            gen_idx: 0,
+        }
+    }
+
+    /// Builds vartime precomputed multiscalar multiplication tables over the
+    /// same `n`-per-party, `m`-party window of the \\(\mathbf G\\) and
+    /// \\(\mathbf H\\) vectors that [`BulletproofGens::G`] and
+    /// [`BulletproofGens::H`] would hand a verifier for that `(n, m)`.
+    ///
+    /// Range-proof verification repeatedly multiscalar-multiplies against
+    /// these fixed generators; reusing the precomputed tables trades a few
+    /// megabytes of resident memory for a measurable per-verification
+    /// speedup. The window must match what verification actually uses: an
+    /// aggregated proof over fewer bits or fewer parties than this
+    /// instance's full capacity reads a strict subset of the generators,
+    /// and a table built from the full vectors would pair scalars against
+    /// the wrong points (or panic on a length mismatch). The Pedersen
+    /// value/blinding bases are deliberately kept out of this table so
+    /// callers can swap those independently.
+    ///
+    /// The returned handle is wrapped in an `Arc` so it can be shared across
+    /// multiple verifiers.
+    pub fn precompute(&self, n: usize, m: usize) -> Arc<BulletproofGensPrecomp> {
+        Arc::new(BulletproofGensPrecomp {
+            G_precomp: VartimeRistrettoPrecomputation::new(self.G(n, m).copied()),
+            H_precomp: VartimeRistrettoPrecomputation::new(self.H(n, m).copied()),
+        })
+    }
+}
+
+/// Precomputed vartime multiscalar multiplication tables for a
+/// `BulletproofGens`'s fixed \\(\mathbf G\\) and \\(\mathbf H\\) vectors.
+///
+/// Built by [`BulletproofGens::precompute`].
+#[allow(non_snake_case)]
+pub struct BulletproofGensPrecomp {
+    G_precomp: VartimeRistrettoPrecomputation,
+    H_precomp: VartimeRistrettoPrecomputation,
+}
+
+impl BulletproofGensPrecomp {
+    /// Computes \\(\sum_i \texttt{scalars}\_i \cdot \mathbf G_i\\) using the
+    /// precomputed table for \\(\mathbf G\\).
+    pub fn multiscalar_mul_g<I>(&self, scalars: I) -> RistrettoPoint
+    where
+        I: IntoIterator,
+        I::Item: Borrow<Scalar>,
+    {
+        self.G_precomp.vartime_multiscalar_mul(scalars)
+    }
+
+    /// Computes \\(\sum_i \texttt{scalars}\_i \cdot \mathbf H_i\\) using the
+    /// precomputed table for \\(\mathbf H\\).
+    pub fn multiscalar_mul_h<I>(&self, scalars: I) -> RistrettoPoint
+    where
+        I: IntoIterator,
+        I::Item: Borrow<Scalar>,
+    {
+        self.H_precomp.vartime_multiscalar_mul(scalars)
+    }
+}
+
+struct AggregatedGensIter<'a> {
+    array: &'a [Vec<RistrettoPoint>],
+    n: usize,
+    m: usize,
+    party_idx: usize,
+    gen_idx: usize,
+}
+
+impl<'a> Iterator for AggregatedGensIter<'a> {
+    type Item = &'a RistrettoPoint;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.gen_idx >= self.n {
+            self.gen_idx = 0;
+            self.party_idx += 1;
+        }
+        if self.party_idx >= self.m {
+            None
+        } else {
+            let cur_gen = self.gen_idx;
+            self.gen_idx += 1;
+            Some(&self.array[self.party_idx][cur_gen])
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let size = (self.m - self.party_idx) * self.n - self.gen_idx;
+        (size, Some(size))
+    }
+}
+
+/// Represents a view of the generators for a specific party, to be used in
+/// the party's single-party range proof.
+pub struct BulletproofGensShare<'a> {
+    /// The parent `BulletproofGens` struct that this is a view into.
+    gens: &'a BulletproofGens,
+    /// Which share this is.
+    share: usize,
+}
+
+impl<'a> BulletproofGensShare<'a> {
+    /// Returns an iterator over this party's share of the \\(\mathbf G\\)
+    /// generators.
+    pub(crate) fn G(&self, n: usize) -> impl Iterator<Item = &'a RistrettoPoint> {
+        GensIter {
+            array: &self.gens.G_vec[self.share],
+            n,
+            gen_idx: 0,
+        }
+    }
+
+    /// Returns an iterator over this party's share of the \\(\mathbf H\\)
+    /// generators.
+    pub(crate) fn H(&self, n: usize) -> impl Iterator<Item = &'a RistrettoPoint> {
+        GensIter {
+            array: &self.gens.H_vec[self.share],
+            n,
             gen_idx: 0,
         }
     }
 }
 
 struct GensIter<'a> {
-    array: &'a Vec<RistrettoPoint>,
+    array: &'a [RistrettoPoint],
     n: usize,
     gen_idx: usize,
 }
@@ -148,3 +492,169 @@ impl<'a> Iterator for GensIter<'a> {
         (size, Some(size))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn try_new_rejects_zero_gens_capacity() {
+        assert_eq!(
+            BulletproofGens::try_new(0, 1).unwrap_err(),
+            RangeProofGeneratorError::EmptyGeneratorSet,
+        );
+    }
+
+    #[test]
+    fn try_new_rejects_zero_party_capacity() {
+        assert_eq!(
+            BulletproofGens::try_new(1, 0).unwrap_err(),
+            RangeProofGeneratorError::EmptyPartySet,
+        );
+    }
+
+    #[test]
+    fn try_new_rejects_oversized_party_capacity() {
+        assert_eq!(
+            BulletproofGens::try_new(1, MAX_GENERATOR_LENGTH + 1).unwrap_err(),
+            RangeProofGeneratorError::MaximumPartyCapacityExceeded,
+        );
+    }
+
+    #[test]
+    fn try_new_rejects_an_oversized_total_capacity() {
+        // Each dimension is individually within `MAX_GENERATOR_LENGTH`, but
+        // their product is not, so this must be rejected before either
+        // outer `Vec` gets allocated.
+        let huge_dimension = MAX_GENERATOR_LENGTH / 2 + 1;
+        assert_eq!(
+            BulletproofGens::try_new(huge_dimension, huge_dimension).unwrap_err(),
+            RangeProofGeneratorError::MaximumGeneratorLengthExceeded,
+        );
+    }
+
+    #[test]
+    fn increase_capacity_rejects_an_oversized_total_capacity() {
+        let mut gens = BulletproofGens::new(1, 100_000);
+        assert_eq!(
+            gens.increase_capacity(100_000).unwrap_err(),
+            RangeProofGeneratorError::MaximumGeneratorLengthExceeded,
+        );
+    }
+
+    #[test]
+    fn aggregated_gens_are_independent_per_party() {
+        let mut gens = BulletproofGens::new(8, 2);
+        let party_0: Vec<_> = gens.share(0).G(8).copied().collect();
+        let party_1: Vec<_> = gens.share(1).G(8).copied().collect();
+        assert_eq!(party_0.len(), 8);
+        assert_eq!(party_1.len(), 8);
+        assert_ne!(party_0, party_1);
+
+        let aggregated: Vec<_> = gens.G(8, 2).copied().collect();
+        assert_eq!(aggregated.len(), 16);
+        assert_eq!(aggregated[..8], party_0[..]);
+        assert_eq!(aggregated[8..], party_1[..]);
+
+        gens.increase_capacity(16).unwrap();
+        let party_0_after_grow: Vec<_> = gens.share(0).G(8).copied().collect();
+        assert_eq!(party_0, party_0_after_grow);
+    }
+
+    #[test]
+    fn precompute_matches_a_non_maximal_aggregation_window() {
+        let mut gens = BulletproofGens::new(64, 2);
+        gens.increase_capacity(128).unwrap();
+
+        let (n, m) = (64, 2);
+        let scalars: Vec<Scalar> = (0..(n * m) as u64).map(Scalar::from).collect();
+
+        let expected = RistrettoPoint::multiscalar_mul(
+            scalars.iter().copied(),
+            gens.G(n, m).copied().collect::<Vec<_>>(),
+        );
+        let actual = gens.precompute(n, m).multiscalar_mul_g(scalars);
+
+        assert_eq!(expected.compress(), actual.compress());
+    }
+
+    #[test]
+    fn value_base_pow_2_uses_the_instances_own_base() {
+        let default_gens = PedersenGens::default();
+        let custom_gens = PedersenGens {
+            B: RistrettoPoint::hash_from_bytes::<Sha3_512>(b"custom value base"),
+            B_blinding: default_gens.B_blinding,
+        };
+
+        assert_ne!(
+            default_gens.value_base_pow_2()[0].compress(),
+            custom_gens.value_base_pow_2()[0].compress(),
+        );
+        assert_eq!(
+            custom_gens.value_base_pow_2()[0].compress(),
+            custom_gens.B.compress()
+        );
+        assert_eq!(
+            custom_gens.value_base_pow_2()[1].compress(),
+            (custom_gens.B + custom_gens.B).compress(),
+        );
+    }
+
+    #[test]
+    fn value_base_pow_2_cache_does_not_grow_without_bound() {
+        // Even with far more distinct bases than `MAX_CACHED_VALUE_BASES`,
+        // every one of them still gets a correct table back...
+        for i in 0..(MAX_CACHED_VALUE_BASES as u64 * 4) {
+            let gens = PedersenGens {
+                B: RistrettoPoint::hash_from_bytes::<Sha3_512>(&i.to_le_bytes()),
+                B_blinding: PedersenGens::default().B_blinding,
+            };
+            assert_eq!(gens.value_base_pow_2()[0].compress(), gens.B.compress());
+        }
+
+        // ...but the cache itself never retains more than the configured
+        // maximum number of distinct bases.
+        assert!(VALUE_BASE_POW_2_CACHE.lock().unwrap().len() <= MAX_CACHED_VALUE_BASES);
+    }
+
+    #[test]
+    fn new_128_provisions_128_bit_generators_per_party() {
+        let gens = BulletproofGens::new_128(1);
+        assert_eq!(gens.gens_capacity, BIT_LENGTH_128);
+        assert_eq!(gens.party_capacity, 1);
+        assert_eq!(gens.share(0).G(BIT_LENGTH_128).count(), BIT_LENGTH_128);
+    }
+
+    #[test]
+    fn new_128_aggregates_across_parties() {
+        let gens = BulletproofGens::new_128(2);
+        assert_eq!(gens.gens_capacity, BIT_LENGTH_128);
+        assert_eq!(gens.party_capacity, 2);
+
+        let aggregated: Vec<_> = gens.G(BIT_LENGTH_128, 2).collect();
+        assert_eq!(aggregated.len(), 2 * BIT_LENGTH_128);
+    }
+
+    #[test]
+    fn commit_u128_matches_manual_scalar_commitment() {
+        let pc_gens = PedersenGens::default();
+        let blinding = Scalar::from(7u64);
+        let value: u128 = (u64::MAX as u128) + 1;
+
+        let expected = pc_gens.commit(scalar_from_u128(value), blinding);
+        let actual = pc_gens.commit_u128(value, blinding);
+
+        assert_eq!(expected.compress(), actual.compress());
+    }
+
+    #[test]
+    fn scalar_from_u128_round_trips_through_bytes() {
+        let value: u128 = 0x1234_5678_9abc_def0_1122_3344_5566_7788;
+        let scalar = scalar_from_u128(value);
+
+        let mut expected_bytes = [0u8; 32];
+        expected_bytes[..16].copy_from_slice(&value.to_le_bytes());
+        assert_eq!(scalar, Scalar::from_bytes_mod_order(expected_bytes));
+        assert_eq!(scalar.to_bytes()[..16], value.to_le_bytes());
+    }
+}